@@ -5,7 +5,7 @@ use crate::ComplexHelper;
 use na::allocator::Allocator;
 use na::dimension::{Const, Dim, DimMin, DimMinimum};
 use na::storage::Storage;
-use na::{DefaultAllocator, Matrix, OMatrix, OVector, Scalar};
+use na::{ComplexField, DefaultAllocator, Matrix, OMatrix, OVector, Scalar};
 
 use lapack;
 
@@ -49,6 +49,21 @@ where
 {
 }
 
+/// The result of [`LU::solve_refined`]: an iteratively-refined solution together with
+/// per-right-hand-side forward- and backward-error bounds reported by LAPACK's `?gerfs`.
+#[derive(Clone, Debug)]
+pub struct RefinedSolution<T: ComplexField, R: Dim, C: Dim>
+where
+    DefaultAllocator: Allocator<R, C> + Allocator<C>,
+{
+    /// The refined solution `x`.
+    pub x: OMatrix<T, R, C>,
+    /// The estimated forward error bound `‖x - true_x‖ / ‖x‖` for each right-hand side.
+    pub ferr: OVector<T::RealField, C>,
+    /// The estimated componentwise backward error bound for each right-hand side.
+    pub berr: OVector<T::RealField, C>,
+}
+
 impl<T: LUScalar, R: Dim, C: Dim> LU<T, R, C>
 where
     T: Zero + One,
@@ -195,7 +210,7 @@ where
         DefaultAllocator: Allocator<R2, C2> + Allocator<R2>,
     {
         let mut res = b.clone_owned();
-        if self.generic_solve_mut(b'T', &mut res) {
+        if self.generic_solve_mut(b'N', &mut res) {
             Some(res)
         } else {
             None
@@ -231,7 +246,7 @@ where
         DefaultAllocator: Allocator<R2, C2> + Allocator<R2>,
     {
         let mut res = b.clone_owned();
-        if self.generic_solve_mut(b'T', &mut res) {
+        if self.generic_solve_mut(T::xgetrs_adjoint_trans(), &mut res) {
             Some(res)
         } else {
             None
@@ -245,7 +260,7 @@ where
     where
         DefaultAllocator: Allocator<R2, C2> + Allocator<R2>,
     {
-        self.generic_solve_mut(b'T', b)
+        self.generic_solve_mut(b'N', b)
     }
 
     /// Solves in-place the linear system `self.transpose() * x = b`, where `x` is the unknown to be
@@ -267,7 +282,7 @@ where
     where
         DefaultAllocator: Allocator<R2, C2> + Allocator<R2>,
     {
-        self.generic_solve_mut(b'T', b)
+        self.generic_solve_mut(T::xgetrs_adjoint_trans(), b)
     }
 }
 
@@ -305,6 +320,205 @@ where
 
         Some(self.lu)
     }
+
+    /// Computes the determinant of the decomposed matrix.
+    #[must_use]
+    pub fn det(&self) -> T
+    where
+        T: ComplexField,
+    {
+        let dim = self.lu.nrows();
+        let mut det = T::one();
+
+        for i in 0..dim {
+            let diag = self.lu[(i, i)];
+
+            if diag.is_zero() {
+                return T::zero();
+            }
+
+            det *= diag;
+        }
+
+        if self.permutation_sign_is_negative() {
+            -det
+        } else {
+            det
+        }
+    }
+
+    /// Computes the natural logarithm of the determinant, split into its sign (or, for complex
+    /// scalars, its unit phase) and the logarithm of its magnitude.
+    ///
+    /// This avoids the overflow or underflow that `det()` can suffer on very large or
+    /// near-singular matrices. If the decomposed matrix is singular, this returns
+    /// `(T::zero(), -inf)`.
+    #[must_use]
+    pub fn ln_det(&self) -> (T, T::RealField)
+    where
+        T: ComplexField,
+    {
+        let dim = self.lu.nrows();
+        let mut ln_magnitude = T::RealField::zero();
+        let mut sign = T::one();
+
+        for i in 0..dim {
+            let diag = self.lu[(i, i)];
+            let norm = diag.abs();
+
+            if norm.is_zero() {
+                // Singular matrix: the magnitude underflows to -inf and the sign is undefined.
+                return (T::zero(), norm.ln());
+            }
+
+            ln_magnitude += norm.ln();
+            sign *= diag.unscale(norm);
+        }
+
+        if self.permutation_sign_is_negative() {
+            sign = -sign;
+        }
+
+        (sign, ln_magnitude)
+    }
+
+    /// Whether the row permutation applied by this decomposition is odd, i.e. whether it
+    /// contributes a factor of `-1` to the determinant.
+    fn permutation_sign_is_negative(&self) -> bool {
+        self.p
+            .iter()
+            .enumerate()
+            .filter(|(i, p)| **p != *i as i32 + 1)
+            .count()
+            % 2
+            != 0
+    }
+
+    /// Estimates the reciprocal condition number (w.r.t. the 1-norm) of the decomposed matrix,
+    /// given the 1-norm `anorm` of the *original* (un-decomposed) matrix.
+    ///
+    /// A result close to machine epsilon indicates an ill-conditioned matrix, meaning `solve`
+    /// and `inverse` should not be trusted. If you don't already have `anorm`, call `rcond_of`
+    /// instead, which computes it from `a` itself.
+    #[must_use]
+    pub fn rcond(&self, anorm: T::RealField) -> T::RealField {
+        self.generic_rcond(b'1', anorm)
+    }
+
+    /// Like `rcond`, but computes the 1-norm of `a` on the fly instead of requiring the caller
+    /// to supply it.
+    ///
+    /// `a` must be the same matrix this `LU` was computed from.
+    #[must_use]
+    pub fn rcond_of(&self, a: &OMatrix<T, D, D>) -> T::RealField {
+        self.generic_rcond(b'1', Self::one_norm(a))
+    }
+
+    /// The 1-norm (maximum absolute column sum) of `a`, as required by `rcond_of`.
+    fn one_norm(a: &OMatrix<T, D, D>) -> T::RealField {
+        a.column_iter()
+            .map(|col| {
+                col.iter()
+                    .fold(T::RealField::zero(), |acc, v| acc + v.abs())
+            })
+            .fold(
+                T::RealField::zero(),
+                |best, norm| {
+                    if norm > best {
+                        norm
+                    } else {
+                        best
+                    }
+                },
+            )
+    }
+
+    fn generic_rcond(&self, norm_type: u8, anorm: T::RealField) -> T::RealField {
+        let dim = self.lu.nrows() as i32;
+        let mut rcond = T::RealField::zero();
+        let mut info = 0;
+
+        let mut work = vec![T::zero(); T::xgecon_work_size(dim) as usize];
+        let mut iwork = vec![0; T::xgecon_iwork_size(dim) as usize];
+        let mut rwork = vec![T::RealField::zero(); T::xgecon_rwork_size(dim) as usize];
+
+        T::xgecon(
+            norm_type,
+            dim,
+            self.lu.as_slice(),
+            dim,
+            anorm,
+            &mut rcond,
+            &mut work,
+            &mut iwork,
+            &mut rwork,
+            &mut info,
+        );
+        lapack_panic!(info);
+
+        rcond
+    }
+
+    /// Solves `a * x = b` using LAPACK's expert driver (`?gerfs`), returning the solution
+    /// together with forward- and backward-error estimates.
+    ///
+    /// `a` must be the same matrix this `LU` was computed from. The initial solution (computed
+    /// the same way as `solve`) is iteratively refined, and the returned `ferr`/`berr` bounds let
+    /// callers judge how trustworthy `x` is without having to compute `rcond` separately.
+    pub fn solve_refined<R2: Dim, C2: Dim, S2>(
+        &self,
+        a: &OMatrix<T, D, D>,
+        b: &Matrix<T, R2, C2, S2>,
+    ) -> Option<RefinedSolution<T, R2, C2>>
+    where
+        S2: Storage<T, R2, C2>,
+        DefaultAllocator: Allocator<R2, C2> + Allocator<R2> + Allocator<C2>,
+    {
+        let mut x = b.clone_owned();
+        if !self.generic_solve_mut(b'N', &mut x) {
+            return None;
+        }
+
+        let b_owned = b.clone_owned();
+        let (_, c2) = b.shape_generic();
+        let mut ferr: OVector<T::RealField, C2> = Matrix::zeros_generic(c2, Const::<1>);
+        let mut berr: OVector<T::RealField, C2> = Matrix::zeros_generic(c2, Const::<1>);
+
+        let dim = self.lu.nrows() as i32;
+        let nrhs = x.ncols() as i32;
+        let mut info = 0;
+
+        let mut work = vec![T::zero(); T::xgerfs_work_size(dim) as usize];
+        let mut iwork = vec![0; T::xgerfs_iwork_size(dim) as usize];
+        let mut rwork = vec![T::RealField::zero(); T::xgerfs_rwork_size(dim) as usize];
+
+        T::xgerfs(
+            b'N',
+            dim,
+            nrhs,
+            a.as_slice(),
+            dim,
+            self.lu.as_slice(),
+            dim,
+            self.p.as_slice(),
+            b_owned.as_slice(),
+            dim,
+            x.as_mut_slice(),
+            dim,
+            ferr.as_mut_slice(),
+            berr.as_mut_slice(),
+            &mut work,
+            &mut iwork,
+            &mut rwork,
+            &mut info,
+        );
+
+        if lapack_test!(info) {
+            Some(RefinedSolution { x, ferr, berr })
+        } else {
+            None
+        }
+    }
 }
 
 /*
@@ -313,7 +527,7 @@ where
  *
  */
 /// Trait implemented by scalars for which Lapack implements the LU decomposition.
-pub trait LUScalar: Scalar + Copy {
+pub trait LUScalar: Scalar + Copy + ComplexField {
     #[allow(missing_docs)]
     fn xgetrf(m: i32, n: i32, a: &mut [Self], lda: i32, ipiv: &mut [i32], info: &mut i32);
     #[allow(missing_docs)]
@@ -342,10 +556,71 @@ pub trait LUScalar: Scalar + Copy {
     );
     #[allow(missing_docs)]
     fn xgetri_work_size(n: i32, a: &mut [Self], lda: i32, ipiv: &[i32], info: &mut i32) -> i32;
+    /// The LAPACK `trans` character to use with `xgetrs` for the adjoint (conjugate transpose)
+    /// solve: `'C'` for complex scalars, `'T'` for real scalars (whose adjoint and transpose
+    /// coincide).
+    fn xgetrs_adjoint_trans() -> u8 {
+        b'T'
+    }
+    #[allow(missing_docs)]
+    fn xgecon(
+        norm: u8,
+        n: i32,
+        a: &[Self],
+        lda: i32,
+        anorm: Self::RealField,
+        rcond: &mut Self::RealField,
+        work: &mut [Self],
+        iwork: &mut [i32],
+        rwork: &mut [Self::RealField],
+        info: &mut i32,
+    );
+    #[allow(missing_docs)]
+    fn xgecon_work_size(n: i32) -> i32;
+    #[allow(missing_docs)]
+    fn xgecon_iwork_size(n: i32) -> i32;
+    /// Size of the `rwork` buffer `xgecon` needs: unused (`0`) for real scalars, `2 * n` for
+    /// complex scalars (`?gecon`'s `RWORK` is twice the length of `?gerfs`'s).
+    fn xgecon_rwork_size(n: i32) -> i32 {
+        let _ = n;
+        0
+    }
+    #[allow(missing_docs)]
+    #[allow(clippy::too_many_arguments)]
+    fn xgerfs(
+        trans: u8,
+        n: i32,
+        nrhs: i32,
+        a: &[Self],
+        lda: i32,
+        af: &[Self],
+        ldaf: i32,
+        ipiv: &[i32],
+        b: &[Self],
+        ldb: i32,
+        x: &mut [Self],
+        ldx: i32,
+        ferr: &mut [Self::RealField],
+        berr: &mut [Self::RealField],
+        work: &mut [Self],
+        iwork: &mut [i32],
+        rwork: &mut [Self::RealField],
+        info: &mut i32,
+    );
+    #[allow(missing_docs)]
+    fn xgerfs_work_size(n: i32) -> i32;
+    #[allow(missing_docs)]
+    fn xgerfs_iwork_size(n: i32) -> i32;
+    /// Size of the `rwork` buffer `xgerfs` needs: unused (`0`) for real scalars, `n` for
+    /// complex scalars.
+    fn xgerfs_rwork_size(n: i32) -> i32 {
+        let _ = n;
+        0
+    }
 }
 
 macro_rules! lup_scalar_impl(
-    ($N: ty, $xgetrf: path, $xlaswp: path, $xgetrs: path, $xgetri: path) => (
+    (real; $N: ty, $xgetrf: path, $xlaswp: path, $xgetrs: path, $xgetri: path, $xgecon: path, $xgerfs: path) => (
         impl LUScalar for $N {
             #[inline]
             fn xgetrf(m: i32, n: i32, a: &mut [Self], lda: i32, ipiv: &mut [i32], info: &mut i32) {
@@ -377,35 +652,314 @@ macro_rules! lup_scalar_impl(
                 unsafe { $xgetri(n, a, lda, ipiv, &mut work, lwork, info); }
                 ComplexHelper::real_part(work[0]) as i32
             }
+
+            #[inline]
+            fn xgecon(norm: u8, n: i32, a: &[Self], lda: i32, anorm: Self::RealField,
+                      rcond: &mut Self::RealField, work: &mut [Self], iwork: &mut [i32],
+                      _rwork: &mut [Self::RealField], info: &mut i32) {
+                unsafe { $xgecon(norm, n, a, lda, anorm, rcond, work, iwork, info) }
+            }
+
+            #[inline]
+            fn xgecon_work_size(n: i32) -> i32 {
+                4 * n
+            }
+
+            #[inline]
+            fn xgecon_iwork_size(n: i32) -> i32 {
+                n
+            }
+
+            #[inline]
+            fn xgerfs(trans: u8, n: i32, nrhs: i32, a: &[Self], lda: i32, af: &[Self], ldaf: i32,
+                      ipiv: &[i32], b: &[Self], ldb: i32, x: &mut [Self], ldx: i32,
+                      ferr: &mut [Self::RealField], berr: &mut [Self::RealField],
+                      work: &mut [Self], iwork: &mut [i32], _rwork: &mut [Self::RealField],
+                      info: &mut i32) {
+                unsafe {
+                    $xgerfs(trans, n, nrhs, a, lda, af, ldaf, ipiv, b, ldb, x, ldx, ferr, berr,
+                            work, iwork, info)
+                }
+            }
+
+            #[inline]
+            fn xgerfs_work_size(n: i32) -> i32 {
+                3 * n
+            }
+
+            #[inline]
+            fn xgerfs_iwork_size(n: i32) -> i32 {
+                n
+            }
         }
-    )
+    );
+
+    (complex; $N: ty, $xgetrf: path, $xlaswp: path, $xgetrs: path, $xgetri: path, $xgecon: path, $xgerfs: path) => (
+        impl LUScalar for $N {
+            #[inline]
+            fn xgetrf(m: i32, n: i32, a: &mut [Self], lda: i32, ipiv: &mut [i32], info: &mut i32) {
+                unsafe { $xgetrf(m, n, a, lda, ipiv, info) }
+            }
+
+            #[inline]
+            fn xlaswp(n: i32, a: &mut [Self], lda: i32, k1: i32, k2: i32, ipiv: &[i32], incx: i32) {
+                unsafe { $xlaswp(n, a, lda, k1, k2, ipiv, incx) }
+            }
+
+            #[inline]
+            fn xgetrs(trans: u8, n: i32, nrhs: i32, a: &[Self], lda: i32, ipiv: &[i32],
+                      b: &mut [Self], ldb: i32, info: &mut i32) {
+                unsafe { $xgetrs(trans, n, nrhs, a, lda, ipiv, b, ldb, info) }
+            }
+
+            #[inline]
+            fn xgetri(n: i32, a: &mut [Self], lda: i32, ipiv: &[i32],
+                      work: &mut [Self], lwork: i32, info: &mut i32) {
+                unsafe { $xgetri(n, a, lda, ipiv, work, lwork, info) }
+            }
+
+            #[inline]
+            fn xgetri_work_size(n: i32, a: &mut [Self], lda: i32, ipiv: &[i32], info: &mut i32) -> i32 {
+                let mut work = [ Zero::zero() ];
+                let lwork = -1 as i32;
+
+                unsafe { $xgetri(n, a, lda, ipiv, &mut work, lwork, info); }
+                ComplexHelper::real_part(work[0]) as i32
+            }
+
+            #[inline]
+            fn xgetrs_adjoint_trans() -> u8 {
+                b'C'
+            }
+
+            #[inline]
+            fn xgecon(norm: u8, n: i32, a: &[Self], lda: i32, anorm: Self::RealField,
+                      rcond: &mut Self::RealField, work: &mut [Self], _iwork: &mut [i32],
+                      rwork: &mut [Self::RealField], info: &mut i32) {
+                unsafe { $xgecon(norm, n, a, lda, anorm, rcond, work, rwork, info) }
+            }
+
+            #[inline]
+            fn xgecon_work_size(n: i32) -> i32 {
+                2 * n
+            }
+
+            #[inline]
+            fn xgecon_iwork_size(n: i32) -> i32 {
+                n
+            }
+
+            #[inline]
+            fn xgecon_rwork_size(n: i32) -> i32 {
+                2 * n
+            }
+
+            #[inline]
+            fn xgerfs(trans: u8, n: i32, nrhs: i32, a: &[Self], lda: i32, af: &[Self], ldaf: i32,
+                      ipiv: &[i32], b: &[Self], ldb: i32, x: &mut [Self], ldx: i32,
+                      ferr: &mut [Self::RealField], berr: &mut [Self::RealField],
+                      work: &mut [Self], _iwork: &mut [i32], rwork: &mut [Self::RealField],
+                      info: &mut i32) {
+                unsafe {
+                    $xgerfs(trans, n, nrhs, a, lda, af, ldaf, ipiv, b, ldb, x, ldx, ferr, berr,
+                            work, rwork, info)
+                }
+            }
+
+            #[inline]
+            fn xgerfs_work_size(n: i32) -> i32 {
+                2 * n
+            }
+
+            #[inline]
+            fn xgerfs_iwork_size(n: i32) -> i32 {
+                n
+            }
+
+            #[inline]
+            fn xgerfs_rwork_size(n: i32) -> i32 {
+                n
+            }
+        }
+    );
 );
 
 lup_scalar_impl!(
+    real;
     f32,
     lapack::sgetrf,
     lapack::slaswp,
     lapack::sgetrs,
-    lapack::sgetri
+    lapack::sgetri,
+    lapack::sgecon,
+    lapack::sgerfs
 );
 lup_scalar_impl!(
+    real;
     f64,
     lapack::dgetrf,
     lapack::dlaswp,
     lapack::dgetrs,
-    lapack::dgetri
+    lapack::dgetri,
+    lapack::dgecon,
+    lapack::dgerfs
 );
 lup_scalar_impl!(
+    complex;
     Complex<f32>,
     lapack::cgetrf,
     lapack::claswp,
     lapack::cgetrs,
-    lapack::cgetri
+    lapack::cgetri,
+    lapack::cgecon,
+    lapack::cgerfs
 );
 lup_scalar_impl!(
+    complex;
     Complex<f64>,
     lapack::zgetrf,
     lapack::zlaswp,
     lapack::zgetrs,
-    lapack::zgetri
+    lapack::zgetri,
+    lapack::zgecon,
+    lapack::zgerfs
 );
+
+#[cfg(test)]
+mod tests {
+    use super::LU;
+    use na::{Complex, Matrix3, Vector3};
+
+    // Intentionally non-symmetric and non-Hermitian so that `solve`, `solve_transpose` and
+    // `solve_conjugate_transpose` each solve a genuinely different system.
+    fn real_matrix() -> Matrix3<f64> {
+        Matrix3::new(4.0, 3.0, 0.0, 1.0, 5.0, 2.0, 0.0, 1.0, 6.0)
+    }
+
+    fn complex_matrix() -> Matrix3<Complex<f64>> {
+        Matrix3::new(
+            Complex::new(4.0, 1.0),
+            Complex::new(3.0, 0.0),
+            Complex::new(0.0, 0.0),
+            Complex::new(1.0, -1.0),
+            Complex::new(5.0, 2.0),
+            Complex::new(2.0, 0.0),
+            Complex::new(0.0, 0.0),
+            Complex::new(1.0, 0.0),
+            Complex::new(6.0, -3.0),
+        )
+    }
+
+    #[test]
+    fn det_matches_known_value() {
+        let m = real_matrix();
+        let lu = LU::new(m);
+
+        // |4 3 0; 1 5 2; 0 1 6| = 4*(5*6 - 2*1) - 3*(1*6 - 2*0) = 94.
+        assert!((lu.det() - 94.0).abs() < 1.0e-9);
+    }
+
+    #[test]
+    fn ln_det_matches_det_for_real_matrix() {
+        let m = real_matrix();
+        let lu = LU::new(m);
+
+        let (sign, ln_abs) = lu.ln_det();
+        assert!((sign * ln_abs.exp() - lu.det()).abs() < 1.0e-9);
+    }
+
+    #[test]
+    fn ln_det_matches_det_for_complex_matrix() {
+        let m = complex_matrix();
+        let lu = LU::new(m);
+
+        let (phase, ln_abs) = lu.ln_det();
+        let recombined = phase * Complex::new(ln_abs.exp(), 0.0);
+        assert!((recombined - lu.det()).norm() < 1.0e-9);
+    }
+
+    #[test]
+    fn rcond_of_identity_is_one() {
+        let m = Matrix3::<f64>::identity();
+        let lu = LU::new(m);
+
+        assert!((lu.rcond_of(&m) - 1.0).abs() < 1.0e-9);
+    }
+
+    #[test]
+    fn rcond_matches_rcond_of() {
+        let m = real_matrix();
+        let lu = LU::new(m);
+
+        // 1-norm (max absolute column sum) of `m`: columns sum to 5, 9 and 8.
+        let anorm = 9.0;
+        assert!((lu.rcond(anorm) - lu.rcond_of(&m)).abs() < 1.0e-9);
+    }
+
+    #[test]
+    fn solve_refined_matches_solve_with_small_error_bounds() {
+        let m = real_matrix();
+        let b = Vector3::new(1.0, 2.0, 3.0);
+        let lu = LU::new(m);
+
+        let x = lu.solve(&b).unwrap();
+        let refined = lu.solve_refined(&m, &b).unwrap();
+
+        assert!((refined.x - x).norm() < 1.0e-9);
+        assert!(refined.ferr[0] < 1.0e-6);
+        assert!(refined.berr[0] < 1.0e-6);
+    }
+
+    #[test]
+    fn solve_solves_m_x_eq_b() {
+        let m = real_matrix();
+        let b = Vector3::new(1.0, 2.0, 3.0);
+        let lu = LU::new(m);
+
+        let x = lu.solve(&b).unwrap();
+        assert!((m * x - b).norm() < 1.0e-10);
+    }
+
+    #[test]
+    fn solve_mut_solves_m_x_eq_b() {
+        let m = real_matrix();
+        let b = Vector3::new(1.0, 2.0, 3.0);
+        let lu = LU::new(m);
+
+        let mut x = b;
+        assert!(lu.solve_mut(&mut x));
+        assert!((m * x - b).norm() < 1.0e-10);
+    }
+
+    #[test]
+    fn solve_transpose_solves_m_transpose_x_eq_b() {
+        let m = real_matrix();
+        let b = Vector3::new(1.0, 2.0, 3.0);
+        let lu = LU::new(m);
+
+        let x = lu.solve_transpose(&b).unwrap();
+        assert!((m.transpose() * x - b).norm() < 1.0e-10);
+
+        let mut x_mut = b;
+        assert!(lu.solve_transpose_mut(&mut x_mut));
+        assert!((m.transpose() * x_mut - b).norm() < 1.0e-10);
+    }
+
+    #[test]
+    fn solve_conjugate_transpose_solves_m_adjoint_x_eq_b() {
+        let m = complex_matrix();
+        let b = Vector3::new(
+            Complex::new(1.0, 0.0),
+            Complex::new(2.0, 1.0),
+            Complex::new(3.0, -2.0),
+        );
+        let lu = LU::new(m);
+
+        let x = lu.solve_conjugate_transpose(&b).unwrap();
+        assert!((m.adjoint() * x - b).norm() < 1.0e-10);
+
+        let mut x_mut = b;
+        assert!(lu.solve_adjoint_mut(&mut x_mut));
+        assert!((m.adjoint() * x_mut - b).norm() < 1.0e-10);
+    }
+}